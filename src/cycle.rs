@@ -0,0 +1,357 @@
+//! An opt-in cycle-collection subsystem for [`Shared`](crate::Shared) graphs.
+//!
+//! `Shared<T>` is just `Rc<RefCell<T>>`, so a graph with strong back-edges (e.g. a child
+//! pointing back at its parent) leaks: nothing ever reaches a strong count of zero. A
+//! [`Collector`] lets such graphs opt into periodic trial-deletion, the same technique used by
+//! reference-counted garbage collectors (Python's `gc`, `Bacon`/`Rajan`'s algorithm): for every
+//! registered node, pretend its strong count excludes strong edges coming from registered nodes
+//! (itself included); whatever is left with a scratch count of zero, and cannot be reached from a
+//! node with a positive count, is reachable only through a cycle and gets torn down via
+//! [`Trace::clear`].
+
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use crate::{Shared, WeakShared};
+
+/// Implemented by types that can take part in cycle collection.
+///
+/// `trace` must report every `Shared`/`WeakShared` field through which `Self` can reach another
+/// registered node, exactly once each. `clear` must drop or replace those same fields (e.g. with
+/// `WeakShared::new()` or an empty collection); the collector calls it on nodes it has
+/// determined are reachable only through a cycle, to break the strong edges keeping them alive.
+pub trait Trace {
+    /// Visits every `Shared`/`WeakShared` child. Must visit exactly the strong children (and
+    /// may additionally report weak ones, which are followed but never counted as keep-alive).
+    fn trace(&self, tracer: &mut Tracer);
+    /// Breaks every strong edge reported by `trace`, so the node stops keeping its neighbours
+    /// alive.
+    fn clear(&mut self);
+}
+
+/// Collects the edges reported by a [`Trace::trace`] call.
+///
+/// Strong and weak edges are kept apart: only strong edges discount a target's scratch count
+/// during [`Collector::break_cycles`]'s trial-deletion pass, while both are followed during its
+/// forward reachability walk.
+#[derive(Default)]
+pub struct Tracer {
+    strong: Vec<NodeId>,
+    weak: Vec<NodeId>,
+}
+
+impl Tracer {
+    /// Reports a strong child, e.g. `tracer.edge(&self.parent)`.
+    pub fn edge<T: ?Sized>(&mut self, child: &Shared<T>) {
+        self.strong.push(NodeId::of(child));
+    }
+    /// Reports a weak child. The edge is followed for reachability purposes, but (being weak)
+    /// never counted as keeping `child`'s pointee alive.
+    pub fn weak_edge<T: ?Sized>(&mut self, child: &WeakShared<T>) {
+        if let Some(shared) = child.upgrade() {
+            self.weak.push(NodeId::of(&shared));
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct NodeId(usize);
+
+impl NodeId {
+    fn of<T: ?Sized>(shared: &Shared<T>) -> NodeId {
+        NodeId(shared.as_ptr() as *const () as usize)
+    }
+}
+
+trait ErasedRoot {
+    fn id(&self) -> Option<NodeId>;
+    /// The root's strong count, with the collector's own (weak) bookkeeping excluded.
+    fn strong_count(&self) -> usize;
+    fn trace(&self) -> Tracer;
+    fn clear(&self);
+    fn alive(&self) -> bool;
+}
+
+struct Root<T: Trace + 'static> {
+    weak: WeakShared<T>,
+}
+
+impl<T: Trace + 'static> ErasedRoot for Root<T> {
+    fn id(&self) -> Option<NodeId> {
+        self.weak.upgrade().map(|shared| NodeId::of(&shared))
+    }
+    fn strong_count(&self) -> usize {
+        match self.weak.upgrade() {
+            // The temporary `Shared` from `upgrade` holds one of these strong references.
+            Some(shared) => shared.strong_count() - 1,
+            None => 0,
+        }
+    }
+    fn trace(&self) -> Tracer {
+        match self.weak.upgrade() {
+            Some(shared) => {
+                let mut tracer = Tracer::default();
+                shared.borrow().trace(&mut tracer);
+                tracer
+            }
+            None => Tracer::default(),
+        }
+    }
+    fn clear(&self) {
+        if let Some(shared) = self.weak.upgrade() {
+            shared.borrow_mut().clear();
+        }
+    }
+    fn alive(&self) -> bool {
+        self.weak.strong_count() > 0
+    }
+}
+
+/// Tracks a set of [`Shared`] roots and breaks reference cycles among them on demand.
+///
+/// Registration is weak: the collector never keeps a registered value alive by itself, and
+/// values that have already been dropped through normal `Rc` bookkeeping are pruned the next
+/// time [`Collector::break_cycles`] runs.
+#[derive(Clone, Default)]
+pub struct Collector {
+    roots: Rc<RefCell<Vec<Box<dyn ErasedRoot>>>>,
+}
+
+impl Collector {
+    pub fn new() -> Collector {
+        Collector::default()
+    }
+
+    /// Registers `shared` as a candidate for cycle collection. `T` must implement [`Trace`] so
+    /// the collector can enumerate (and, if needed, clear) its `Shared`/`WeakShared` fields.
+    pub fn register<T: Trace + 'static>(&self, shared: &Shared<T>) {
+        self.roots.borrow_mut().push(Box::new(Root { weak: shared.downgrade() }));
+    }
+
+    /// Runs one trial-deletion pass: every registered node starts from its real strong count,
+    /// minus one for every strong edge it receives from a registered node (itself included).
+    /// Anything left at zero is kept alive only by edges from inside the registered set; any such
+    /// node still reachable (through strong or weak edges) from a node with a positive count is
+    /// kept by the forward reachability walk below. The rest are torn down via [`Trace::clear`].
+    pub fn break_cycles(&self) {
+        let mut roots = self.roots.borrow_mut();
+        roots.retain(|root| root.alive());
+
+        let mut index_of = HashMap::new();
+        let mut scratch = HashMap::new();
+        for (i, root) in roots.iter().enumerate() {
+            if let Some(id) = root.id() {
+                index_of.insert(id, i);
+                scratch.insert(id, root.strong_count());
+            }
+        }
+
+        // Only strong edges discount a target's scratch count: a weak back-pointer is followed
+        // below for reachability, but (per `Trace::trace`'s contract) never counts as keeping
+        // its target alive.
+        for root in roots.iter() {
+            for edge in root.trace().strong {
+                if let Some(count) = scratch.get_mut(&edge) {
+                    *count = count.saturating_sub(1);
+                }
+            }
+        }
+
+        let mut live = HashSet::new();
+        let mut pending: Vec<NodeId> = scratch
+            .iter()
+            .filter(|&(_, &count)| count > 0)
+            .map(|(&id, _)| id)
+            .collect();
+        while let Some(id) = pending.pop() {
+            if !live.insert(id) {
+                continue;
+            }
+            if let Some(&i) = index_of.get(&id) {
+                let tracer = roots[i].trace();
+                pending.extend(tracer.strong);
+                pending.extend(tracer.weak);
+            }
+        }
+
+        for root in roots.iter() {
+            if let Some(id) = root.id() {
+                if !live.contains(&id) {
+                    root.clear();
+                }
+            }
+        }
+    }
+}
+
+/// A type alias for [`SharedGuard`], for callers who think of it as "a `Shared` scoped to a
+/// collector" rather than as an RAII guard.
+pub type ScopedShared = SharedGuard;
+
+/// An RAII scope tied to a [`Collector`]: dropping it runs [`Collector::break_cycles`], so a
+/// traversal that builds up temporary cycles can clean them up as soon as it's done.
+pub struct SharedGuard {
+    collector: Collector,
+}
+
+impl SharedGuard {
+    pub fn new(collector: Collector) -> SharedGuard {
+        SharedGuard { collector }
+    }
+    /// Registers `shared` with this guard's collector. See [`Collector::register`].
+    pub fn register<T: Trace + 'static>(&self, shared: &Shared<T>) {
+        self.collector.register(shared);
+    }
+}
+
+impl Drop for SharedGuard {
+    fn drop(&mut self) {
+        self.collector.break_cycles();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Node {
+        child: Option<Shared<Node>>,
+    }
+
+    impl Trace for Node {
+        fn trace(&self, tracer: &mut Tracer) {
+            if let Some(child) = &self.child {
+                tracer.edge(child);
+            }
+        }
+        fn clear(&mut self) {
+            self.child = None;
+        }
+    }
+
+    #[test]
+    fn breaks_a_self_cycle() {
+        let collector = Collector::new();
+        let a = Shared::new(Node { child: None });
+        collector.register(&a);
+        a.borrow_mut().child = Some(a.clone());
+
+        let weak = a.downgrade();
+        drop(a);
+        assert_eq!(weak.strong_count(), 1);
+
+        collector.break_cycles();
+        assert_eq!(weak.strong_count(), 0);
+    }
+
+    #[test]
+    fn breaks_a_two_node_cycle() {
+        let collector = Collector::new();
+        let a = Shared::new(Node { child: None });
+        let b = Shared::new(Node { child: None });
+        collector.register(&a);
+        collector.register(&b);
+        a.borrow_mut().child = Some(b.clone());
+        b.borrow_mut().child = Some(a.clone());
+
+        let weak_a = a.downgrade();
+        let weak_b = b.downgrade();
+        drop(a);
+        drop(b);
+        assert_eq!(weak_a.strong_count(), 1);
+        assert_eq!(weak_b.strong_count(), 1);
+
+        collector.break_cycles();
+        assert_eq!(weak_a.strong_count(), 0);
+        assert_eq!(weak_b.strong_count(), 0);
+    }
+
+    #[test]
+    fn leaves_externally_referenced_nodes_alone() {
+        let collector = Collector::new();
+        let a = Shared::new(Node { child: None });
+        collector.register(&a);
+        a.borrow_mut().child = Some(a.clone());
+
+        collector.break_cycles();
+
+        assert_eq!(a.strong_count(), 2);
+        assert!(a.borrow().child.is_some());
+    }
+
+    struct Leaf {
+        marker: i32,
+    }
+
+    impl Trace for Leaf {
+        fn trace(&self, _tracer: &mut Tracer) {}
+        fn clear(&mut self) {
+            self.marker = 0;
+        }
+    }
+
+    struct BackrefNode {
+        sibling: Option<Shared<BackrefNode>>,
+        weak_parent: Option<WeakShared<Leaf>>,
+    }
+
+    impl Trace for BackrefNode {
+        fn trace(&self, tracer: &mut Tracer) {
+            if let Some(sibling) = &self.sibling {
+                tracer.edge(sibling);
+            }
+            if let Some(parent) = &self.weak_parent {
+                tracer.weak_edge(parent);
+            }
+        }
+        fn clear(&mut self) {
+            self.sibling = None;
+            self.weak_parent = None;
+        }
+    }
+
+    #[test]
+    fn weak_edge_does_not_keep_its_target_alive() {
+        let collector = Collector::new();
+
+        // `x` is held externally for the whole test, and is not itself part of any cycle.
+        let x = Shared::new(Leaf { marker: 1 });
+        collector.register(&x);
+
+        let weak_y = {
+            // `y`/`z` form a real cycle, reachable only through each other once the local
+            // handles below are dropped; `y` also carries a *weak* back-pointer to `x`.
+            let y = Shared::new(BackrefNode { sibling: None, weak_parent: Some(x.downgrade()) });
+            let z = Shared::new(BackrefNode { sibling: None, weak_parent: None });
+            collector.register(&y);
+            collector.register(&z);
+            y.borrow_mut().sibling = Some(z.clone());
+            z.borrow_mut().sibling = Some(y.clone());
+            y.downgrade()
+        };
+        assert_eq!(weak_y.strong_count(), 1);
+
+        collector.break_cycles();
+
+        // The `y`/`z` cycle is unreachable from anywhere else, so it must be torn down...
+        assert_eq!(weak_y.strong_count(), 0);
+        // ...but `y`'s weak back-pointer to `x` must never have discounted `x`'s scratch
+        // count: `x` is alive and non-cyclic, so it must survive, untouched, by the collector.
+        assert_eq!(x.borrow().marker, 1);
+    }
+
+    #[test]
+    fn shared_guard_breaks_cycles_on_drop() {
+        let collector = Collector::new();
+        let weak = {
+            let guard = SharedGuard::new(collector.clone());
+            let a = Shared::new(Node { child: None });
+            guard.register(&a);
+            a.borrow_mut().child = Some(a.clone());
+            a.downgrade()
+        };
+
+        assert_eq!(weak.strong_count(), 0);
+    }
+}
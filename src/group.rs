@@ -0,0 +1,125 @@
+//! A multi-entrance ("me-cell"-style) borrow mode for [`Shared`](crate::Shared) graphs.
+//!
+//! Ordinary `Shared<T>` borrows are tracked per-cell, by the `RefCell`'s own flag: a cell that's
+//! already read can be read again (the usual `RefCell` rule), but nothing ties the borrow-state
+//! of one cell to another. A [`SharedGroup`] gives a set of cells, created via
+//! [`Shared::new_in_group`](crate::Shared::new_in_group), one borrow-state counter instead: any
+//! number of shared reads across the whole group succeed at once — including ones that re-enter
+//! a cell or the group while it's already read — while a single group-wide mutable borrow
+//! excludes every other borrow anywhere in the group. This lets a recursive traversal over a
+//! shared graph (e.g. one with back-edges) borrow its way back to an already-read node without
+//! panicking on that node's own `RefCell` flag.
+
+use std::cell::Cell;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
+
+/// Shared borrow-state for every cell created in the same group via
+/// [`Shared::new_in_group`](crate::Shared::new_in_group).
+#[derive(Clone, Default)]
+pub struct SharedGroup {
+    // 0 = unborrowed, n > 0 = n live shared borrows, -1 = exclusively (mutably) borrowed.
+    pub(crate) state: Rc<Cell<isize>>,
+}
+
+impl SharedGroup {
+    pub fn new() -> SharedGroup {
+        SharedGroup::default()
+    }
+}
+
+/// The error returned by [`Shared::group_try_borrow`](crate::Shared::group_try_borrow) and
+/// [`Shared::group_try_borrow_mut`](crate::Shared::group_try_borrow_mut).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MeBorrowError {
+    /// The cell wasn't created with `Shared::new_in_group`, so it has no group borrow-state to
+    /// check.
+    NotInGroup,
+    /// The group is already borrowed in a way that conflicts with the requested borrow.
+    Borrowed,
+}
+
+impl fmt::Display for MeBorrowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MeBorrowError::NotInGroup => {
+                write!(f, "cell was not created with `Shared::new_in_group`")
+            }
+            MeBorrowError::Borrowed => write!(f, "already borrowed"),
+        }
+    }
+}
+
+impl std::error::Error for MeBorrowError {}
+
+/// A read guard returned by [`Shared::group_try_borrow`](crate::Shared::group_try_borrow).
+pub struct GroupRef<'a, T: ?Sized> {
+    value: &'a T,
+    group: SharedGroup,
+}
+
+impl<'a, T: ?Sized> GroupRef<'a, T> {
+    pub(crate) fn new(value: &'a T, group: SharedGroup) -> GroupRef<'a, T> {
+        GroupRef { value, group }
+    }
+}
+
+impl<'a, T: ?Sized> Deref for GroupRef<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<'a, T: ?Sized> Drop for GroupRef<'a, T> {
+    fn drop(&mut self) {
+        self.group.state.set(self.group.state.get() - 1);
+    }
+}
+
+impl<'a, T: ?Sized + fmt::Debug> fmt::Debug for GroupRef<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.value, f)
+    }
+}
+
+/// A write guard returned by
+/// [`Shared::group_try_borrow_mut`](crate::Shared::group_try_borrow_mut).
+pub struct GroupRefMut<'a, T: ?Sized> {
+    value: &'a mut T,
+    group: SharedGroup,
+}
+
+impl<'a, T: ?Sized> GroupRefMut<'a, T> {
+    pub(crate) fn new(value: &'a mut T, group: SharedGroup) -> GroupRefMut<'a, T> {
+        GroupRefMut { value, group }
+    }
+}
+
+impl<'a, T: ?Sized> Deref for GroupRefMut<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<'a, T: ?Sized> DerefMut for GroupRefMut<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.value
+    }
+}
+
+impl<'a, T: ?Sized> Drop for GroupRefMut<'a, T> {
+    fn drop(&mut self) {
+        self.group.state.set(0);
+    }
+}
+
+impl<'a, T: ?Sized + fmt::Debug> fmt::Debug for GroupRefMut<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.value, f)
+    }
+}
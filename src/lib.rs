@@ -1,35 +1,184 @@
+pub mod cycle;
+pub mod group;
+
 use std::{
-    cell::{RefCell,Ref, RefMut},
+    cell::{BorrowError, BorrowMutError, RefCell,Ref, RefMut},
     fmt,
+    hash::{Hash, Hasher},
     ops::Deref,
     rc::{Rc, Weak},
 };
 
-pub struct Shared<T> {
-    v: Rc<RefCell<T>>
+use group::{GroupRef, GroupRefMut, MeBorrowError, SharedGroup};
+
+pub struct Shared<T: ?Sized> {
+    v: Rc<RefCell<T>>,
+    group: Option<SharedGroup>,
 }
 
 impl <T> Shared<T> {
     pub fn new(t: T)-> Shared<T> {
-        Shared{v: Rc::new(RefCell::new(t))}
+        Shared{v: Rc::new(RefCell::new(t)), group: None}
+    }
+    /// Creates a value whose multi-entrance borrow methods ([`Shared::group_try_borrow`],
+    /// [`Shared::group_try_borrow_mut`]) are tracked through `group`'s shared borrow-state
+    /// instead of this cell's own `RefCell` flag. See [`SharedGroup`] for the semantics this
+    /// enables.
+    pub fn new_in_group(group: &SharedGroup, t: T) -> Shared<T> {
+        Shared{v: Rc::new(RefCell::new(t)), group: Some(group.clone())}
     }
+    /// Consumes the `Shared`, returning the inner value, panicking if this is not the last
+    /// strong reference.
+    pub fn into_inner(self) -> T {
+        match Rc::try_unwrap(self.v) {
+            Ok(cell) => cell.into_inner(),
+            Err(_) => panic!("into_inner: other `Shared` references to the same value exist"),
+        }
+    }
+    /// Consumes the `Shared`, returning the inner value if this is the last strong reference,
+    /// or the `Shared` itself otherwise.
+    pub fn try_unwrap(self) -> Result<T, Shared<T>> {
+        let group = self.group.clone();
+        match Rc::try_unwrap(self.v) {
+            Ok(cell) => Ok(cell.into_inner()),
+            Err(rc) => Err(Shared{v: rc, group}),
+        }
+    }
+}
+
+impl <T: ?Sized> Shared<T> {
+    /// Wraps an existing `Rc<RefCell<T>>`, most commonly one that has already been unsized to a
+    /// trait object (e.g. `Rc::new(RefCell::new(x)) as Rc<RefCell<dyn Trait>>`), the way `Rc`
+    /// itself supports on stable Rust.
+    ///
+    /// `Shared<T>` can't implement `CoerceUnsized` to make `Shared<Concrete>` coerce to
+    /// `Shared<dyn Trait>` automatically: that trait (and the `Unsize` marker it needs) is
+    /// perma-unstable, and gating this crate on nightly for every user just to support the
+    /// handful who need trait-object nodes isn't worth it. Unsizing the `Rc` first and wrapping
+    /// it with `new_from` gets the same result explicitly, on stable.
     pub fn new_from(rc: Rc<RefCell<T>>) -> Shared<T> {
-        Shared{v: rc}
+        Shared{v: rc, group: None}
     }
-    pub fn borrow(&self) -> Ref<T> {
+    /// Immutably borrows the value, panicking if it's already mutably borrowed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this cell was created with [`Shared::new_in_group`]. This cell's own
+    /// `RefCell` flag plays no part in a grouped cell's borrow-state (see [`SharedGroup`]), so
+    /// letting this method go through it would let a live [`Shared::group_try_borrow_mut`]
+    /// guard coexist with a live `Ref` here — call [`Shared::group_try_borrow`] instead.
+    pub fn borrow(&self) -> Ref<'_, T> {
+        self.assert_not_grouped("Shared::borrow", "Shared::group_try_borrow");
         self.v.borrow()
     }
-    pub fn borrow_mut(&self) -> RefMut<T> {
+    /// Mutably borrows the value, panicking if it's already borrowed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this cell was created with [`Shared::new_in_group`]; see [`Shared::borrow`].
+    /// Call [`Shared::group_try_borrow_mut`] instead.
+    pub fn borrow_mut(&self) -> RefMut<'_, T> {
+        self.assert_not_grouped("Shared::borrow_mut", "Shared::group_try_borrow_mut");
         self.v.borrow_mut()
     }
+    /// Borrows the value and projects a reference to one of its fields, the way
+    /// [`Ref::map`] does, so callers don't need to hand out a guard to the whole value just to
+    /// read a sub-field of it.
+    pub fn borrow_map<U: ?Sized, F: FnOnce(&T) -> &U>(&self, f: F) -> Ref<'_, U> {
+        Ref::map(self.borrow(), f)
+    }
+    /// Like [`Shared::borrow_map`], but borrows mutably, the way [`RefMut::map`] does.
+    pub fn borrow_map_mut<U: ?Sized, F: FnOnce(&mut T) -> &mut U>(&self, f: F) -> RefMut<'_, U> {
+        RefMut::map(self.borrow_mut(), f)
+    }
+    /// Like [`Shared::borrow`], but returns an error instead of panicking if the value is
+    /// already mutably borrowed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this cell was created with [`Shared::new_in_group`]; see [`Shared::borrow`].
+    /// Call [`Shared::group_try_borrow`] instead.
+    pub fn try_borrow(&self) -> Result<Ref<'_, T>, BorrowError> {
+        self.assert_not_grouped("Shared::try_borrow", "Shared::group_try_borrow");
+        self.v.try_borrow()
+    }
+    /// Like [`Shared::borrow_mut`], but returns an error instead of panicking if the value is
+    /// already borrowed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this cell was created with [`Shared::new_in_group`]; see [`Shared::borrow`].
+    /// Call [`Shared::group_try_borrow_mut`] instead.
+    pub fn try_borrow_mut(&self) -> Result<RefMut<'_, T>, BorrowMutError> {
+        self.assert_not_grouped("Shared::try_borrow_mut", "Shared::group_try_borrow_mut");
+        self.v.try_borrow_mut()
+    }
+    /// Panics if this cell was created with [`Shared::new_in_group`]: its `RefCell` borrow flag
+    /// is meaningless for a grouped cell, since [`Shared::group_try_borrow`] and
+    /// [`Shared::group_try_borrow_mut`] never touch it, so letting it gate access here would
+    /// let a plain borrow alias a live group borrow (or vice versa). Used by both `Shared` and
+    /// `WeakShared`'s non-group-aware borrow methods, hence the fully qualified `method`/
+    /// `group_method` names rather than ones this type would have to prefix itself.
+    fn assert_not_grouped(&self, method: &'static str, group_method: &'static str) {
+        assert!(
+            self.group.is_none(),
+            "called `{method}` on a cell created with `Shared::new_in_group`; use \
+             `{group_method}` instead"
+        );
+    }
     pub fn as_ptr(&self) -> *mut T {
         self.v.as_ptr()
     }
-    pub fn clone(&self) -> Self {
-        Self {v: self.v.clone()}
+    /// Dereferences to the wrapped value without going through `RefCell`'s runtime borrow
+    /// checks.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that no `RefMut` borrow of this value is alive for as long as
+    /// the returned reference is used.
+    pub unsafe fn deref_unchecked(&self) -> &T {
+        unsafe { &*self.as_ptr() }
+    }
+    /// Immutably borrows the value through this cell's [`SharedGroup`], the way
+    /// [`Shared::try_borrow`] does through the cell's own `RefCell` flag, except that the
+    /// borrow-state is shared by every cell in the group: any number of such borrows succeed at
+    /// once, including ones that re-enter a cell (or the group as a whole) that's already read,
+    /// and only a live [`Shared::group_try_borrow_mut`] anywhere in the group blocks them.
+    ///
+    /// Returns `Err(MeBorrowError::NotInGroup)` if this cell wasn't created with
+    /// [`Shared::new_in_group`].
+    pub fn group_try_borrow(&self) -> Result<GroupRef<'_, T>, MeBorrowError> {
+        let group = self.group.as_ref().ok_or(MeBorrowError::NotInGroup)?;
+        let count = group.state.get();
+        if count < 0 {
+            return Err(MeBorrowError::Borrowed);
+        }
+        group.state.set(count + 1);
+        // SAFETY: the group's borrow-state, just incremented above, is what enforces
+        // exclusivity for this cell's data now, in place of its own `RefCell` flag: no
+        // `group_try_borrow_mut` on any cell in the group can succeed until every `GroupRef`
+        // handed out against it (including this one) is dropped.
+        Ok(GroupRef::new(unsafe { &*self.as_ptr() }, group.clone()))
+    }
+    /// Mutably borrows the value through this cell's [`SharedGroup`]. A group-wide mutable
+    /// borrow excludes every other borrow, shared or mutable, anywhere in the group.
+    ///
+    /// Returns `Err(MeBorrowError::NotInGroup)` if this cell wasn't created with
+    /// [`Shared::new_in_group`], or `Err(MeBorrowError::Borrowed)` if the group is already
+    /// borrowed.
+    pub fn group_try_borrow_mut(&self) -> Result<GroupRefMut<'_, T>, MeBorrowError> {
+        let group = self.group.as_ref().ok_or(MeBorrowError::NotInGroup)?;
+        if group.state.get() != 0 {
+            return Err(MeBorrowError::Borrowed);
+        }
+        group.state.set(-1);
+        // SAFETY: see `group_try_borrow`; setting the group's state to exclusive above
+        // guarantees no other group borrow can succeed until the returned `GroupRefMut` is
+        // dropped.
+        Ok(GroupRefMut::new(unsafe { &mut *self.as_ptr() }, group.clone()))
     }
     pub fn downgrade(&self) -> WeakShared<T> {
-        WeakShared::new_from(Rc::downgrade(&self.v))
+        WeakShared{v: Rc::downgrade(&self.v), group: self.group.clone()}
     }
     pub fn strong_count(&self) -> usize {
         Rc::strong_count(&self.v)
@@ -39,44 +188,66 @@ impl <T> Shared<T> {
     }
 }
 
-impl <T: fmt::Display> fmt::Display for Shared<T> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.deref())
+impl <T: ?Sized> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        Self {v: self.v.clone(), group: self.group.clone()}
     }
 }
 
-impl <T: fmt::Debug> fmt::Debug for Shared<T> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self.deref())
+impl <T: ?Sized> PartialEq for Shared<T> {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.v, &other.v)
     }
 }
 
-impl <'a,T> Deref for Shared<T>{
-    type Target = T;
+impl <T: ?Sized> Eq for Shared<T> {}
 
-    #[inline]
-    fn deref(&self) -> &T {
-        unsafe {self.as_ptr().as_ref().unwrap()}
+/// Hashes by pointer identity, the same identity [`PartialEq`] uses (`Rc::ptr_eq`), rather than
+/// by the wrapped value. Hashing the value itself would be unsound for a type whose whole point
+/// is interior mutability: a `Shared` stored in a `HashSet`/`HashMap` key position would land in
+/// the wrong bucket the moment it (or an alias of it) was mutated through `borrow_mut`.
+impl <T: ?Sized> Hash for Shared<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (Rc::as_ptr(&self.v) as *const ()).hash(state)
     }
 }
 
-pub struct WeakShared<T> {
-    v: Weak<RefCell<T>>
+impl <T: Default> Default for Shared<T> {
+    fn default() -> Self {
+        Shared::new(T::default())
+    }
+}
+
+impl <T: ?Sized + fmt::Display> fmt::Display for Shared<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.borrow())
+    }
+}
+
+impl <T: ?Sized + fmt::Debug> fmt::Debug for Shared<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.borrow())
+    }
+}
+
+pub struct WeakShared<T: ?Sized> {
+    v: Weak<RefCell<T>>,
+    group: Option<SharedGroup>,
 }
 
 impl <T> WeakShared<T> {
     pub fn new()-> WeakShared<T> {
-        WeakShared{v: Weak::new()}
+        WeakShared{v: Weak::new(), group: None}
     }
+}
+
+impl <T: ?Sized> WeakShared<T> {
     pub fn new_from(weak: Weak<RefCell<T>>) -> WeakShared<T> {
-        WeakShared{v: weak}
-    }
-    pub fn clone(&self) -> Self {
-        Self {v: self.v.clone()}
+        WeakShared{v: weak, group: None}
     }
     pub fn upgrade(&self) -> Option<Shared<T>> {
         if let Some(rc) = self.v.upgrade() {
-            Some(Shared::new_from(rc))
+            Some(Shared{v: rc, group: self.group.clone()})
         } else {
             None
         }
@@ -90,34 +261,159 @@ impl <T> WeakShared<T> {
     pub fn as_ptr(&self) -> *const T {
         self.upgrade().unwrap().as_ptr()
     }
+    /// Dereferences to the wrapped value without going through `RefCell`'s runtime borrow
+    /// checks.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the pointee is still alive, and that no `RefMut` borrow of
+    /// it is alive for as long as the returned reference is used.
+    pub unsafe fn deref_unchecked(&self) -> &T {
+        unsafe { &*self.as_ptr() }
+    }
+}
+
+// `WeakRef`/`WeakRefMut` stash the upgraded `Shared<T>` next to a borrow whose lifetime has
+// been relabelled `'static` (see the safety comments below), so `T` itself must not borrow
+// anything with a shorter lifetime.
+impl <T: ?Sized + 'static> WeakShared<T> {
+    /// Like [`Shared::try_borrow`], but on the pointee of a `WeakShared`.
+    ///
+    /// Returns `None` if the value has already been dropped, or `Some(Err(_))` if it is
+    /// currently mutably borrowed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pointee was created with [`Shared::new_in_group`]; see
+    /// [`Shared::borrow`]. Upgrade and call [`Shared::group_try_borrow`] instead.
+    pub fn try_borrow(&self) -> Option<Result<WeakRef<T>, BorrowError>> {
+        let shared = self.upgrade()?;
+        shared.assert_not_grouped("WeakShared::try_borrow", "Shared::group_try_borrow");
+        // SAFETY: `cell` points into the `RefCell` allocated behind `shared.v`. That
+        // allocation is kept alive for as long as `shared` is, and `shared` is moved into the
+        // returned `WeakRef` alongside the borrow below, so the data it points to outlives the
+        // (relabelled) `'static` borrow for as long as the guard exists.
+        let cell: &'static RefCell<T> = unsafe { &*Rc::as_ptr(&shared.v) };
+        let result = match cell.try_borrow() {
+            Ok(r) => Ok(WeakRef { shared, r }),
+            Err(e) => Err(e),
+        };
+        Some(result)
+    }
+    /// Like [`Shared::try_borrow_mut`], but on the pointee of a `WeakShared`.
+    ///
+    /// Returns `None` if the value has already been dropped, or `Some(Err(_))` if it is
+    /// currently borrowed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pointee was created with [`Shared::new_in_group`]; see
+    /// [`Shared::borrow`]. Upgrade and call [`Shared::group_try_borrow_mut`] instead.
+    pub fn try_borrow_mut(&self) -> Option<Result<WeakRefMut<T>, BorrowMutError>> {
+        let shared = self.upgrade()?;
+        shared.assert_not_grouped("WeakShared::try_borrow_mut", "Shared::group_try_borrow_mut");
+        // SAFETY: see `WeakShared::try_borrow`.
+        let cell: &'static RefCell<T> = unsafe { &*Rc::as_ptr(&shared.v) };
+        let result = match cell.try_borrow_mut() {
+            Ok(r) => Ok(WeakRefMut { shared, r }),
+            Err(e) => Err(e),
+        };
+        Some(result)
+    }
+}
+
+/// A read guard returned by [`WeakShared::try_borrow`].
+///
+/// Holding onto this guard also keeps the pointee alive, even if every `Shared` pointing at it
+/// is dropped in the meantime.
+pub struct WeakRef<T: ?Sized + 'static> {
+    // `r` must be dropped before `shared`: its `Drop` impl touches the `RefCell`'s borrow
+    // flag, which lives in the allocation `shared` keeps alive. Rust drops fields in
+    // declaration order, so `r` is listed first.
+    r: Ref<'static, T>,
+    #[allow(dead_code)]
+    shared: Shared<T>,
+}
+
+impl <T: ?Sized + 'static> Deref for WeakRef<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.r
+    }
+}
+
+impl <T: ?Sized + 'static + fmt::Debug> fmt::Debug for WeakRef<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&*self.r, f)
+    }
+}
+
+/// A write guard returned by [`WeakShared::try_borrow_mut`].
+///
+/// Holding onto this guard also keeps the pointee alive, even if every `Shared` pointing at it
+/// is dropped in the meantime.
+pub struct WeakRefMut<T: ?Sized + 'static> {
+    // See the field ordering note on `WeakRef`.
+    r: RefMut<'static, T>,
+    #[allow(dead_code)]
+    shared: Shared<T>,
 }
 
-impl <'a,T> Deref for WeakShared<T>{
+impl <T: ?Sized + 'static> Deref for WeakRefMut<T> {
     type Target = T;
 
-    #[inline]
     fn deref(&self) -> &T {
-        unsafe {self.as_ptr().as_ref().unwrap()}
+        &self.r
+    }
+}
+
+impl <T: ?Sized + 'static> std::ops::DerefMut for WeakRefMut<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.r
     }
 }
 
-impl <T: fmt::Display> fmt::Display for WeakShared<T> {
+impl <T: ?Sized + 'static + fmt::Debug> fmt::Debug for WeakRefMut<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if self.weak_count() > 0 {
-            write!(f, "{}", self.deref())
-        } else {
-            write!(f, "No ref")
+        fmt::Debug::fmt(&*self.r, f)
+    }
+}
+
+impl <T: ?Sized> Clone for WeakShared<T> {
+    fn clone(&self) -> Self {
+        Self {v: self.v.clone(), group: self.group.clone()}
+    }
+}
+
+impl <T: ?Sized> PartialEq for WeakShared<T> {
+    fn eq(&self, other: &Self) -> bool {
+        Weak::ptr_eq(&self.v, &other.v)
+    }
+}
+
+impl <T: ?Sized> Eq for WeakShared<T> {}
+
+impl <T> Default for WeakShared<T> {
+    fn default() -> Self {
+        WeakShared::new()
+    }
+}
+
+impl <T: ?Sized + fmt::Display> fmt::Display for WeakShared<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.upgrade() {
+            Some(shared) => write!(f, "{}", shared.borrow()),
+            None => write!(f, "No ref"),
         }
-        
     }
 }
 
-impl <T: fmt::Debug> fmt::Debug for WeakShared<T> {
+impl <T: ?Sized + fmt::Debug> fmt::Debug for WeakShared<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if self.weak_count() > 0 {
-            write!(f, "{:?}", self.deref())
-        } else {
-            write!(f, "No ref")
+        match self.upgrade() {
+            Some(shared) => write!(f, "{:?}", shared.borrow()),
+            None => write!(f, "No ref"),
         }
     }
 }
@@ -172,4 +468,218 @@ mod tests {
 
         assert_eq!(&*shared.borrow(), "Hello, world!");
     }
+    #[test]
+    fn eq_shared() {
+        let shared1 = Shared::new("Hello".to_string());
+        let shared2 = shared1.clone();
+        let shared3 = Shared::new("Hello".to_string());
+
+        assert_eq!(shared1, shared2);
+        assert_ne!(shared1, shared3);
+    }
+    #[test]
+    // `Shared<T>` is interior-mutable by design; this test exists to confirm that's still sound
+    // as a hash-map/set key (`Hash`/`Eq` agree on pointer identity), which is exactly what
+    // `clippy::mutable_key_type` can't see.
+    #[allow(clippy::mutable_key_type)]
+    fn hash_stays_consistent_with_ptr_eq_across_mutation() {
+        use std::collections::HashSet;
+
+        let shared = Shared::new(1);
+        let mut set = HashSet::new();
+        set.insert(shared.clone());
+
+        *shared.borrow_mut() += 1;
+
+        assert!(set.contains(&shared));
+    }
+    #[test]
+    fn default_shared() {
+        let shared = Shared::<String>::default();
+
+        assert_eq!(&*shared.borrow(), "");
+    }
+    #[test]
+    fn into_inner() {
+        let shared = Shared::new("Hello".to_string());
+
+        assert_eq!(shared.into_inner(), "Hello");
+    }
+    #[test]
+    fn try_unwrap_fails_with_other_reference() {
+        let shared1 = Shared::new("Hello".to_string());
+        let shared2 = shared1.clone();
+
+        let shared1 = shared1.try_unwrap().unwrap_err();
+        drop(shared2);
+
+        assert_eq!(shared1.try_unwrap().unwrap(), "Hello".to_string());
+    }
+    #[test]
+    fn try_borrow_fails_while_borrowed_mut() {
+        let shared = Shared::new("Hello".to_string());
+        let _guard = shared.borrow_mut();
+
+        assert!(shared.try_borrow().is_err());
+    }
+    #[test]
+    fn try_borrow_mut_fails_while_borrowed() {
+        let shared = Shared::new("Hello".to_string());
+        let _guard = shared.borrow();
+
+        assert!(shared.try_borrow_mut().is_err());
+    }
+    #[test]
+    fn weak_try_borrow() {
+        let shared = Shared::new("Hello".to_string());
+        let weak = shared.downgrade();
+
+        assert_eq!(&*weak.try_borrow().unwrap().unwrap(), "Hello");
+    }
+    #[test]
+    fn weak_try_borrow_after_drop() {
+        let shared = Shared::new("Hello".to_string());
+        let weak = shared.downgrade();
+        drop(shared);
+
+        assert!(weak.try_borrow().is_none());
+    }
+    #[test]
+    fn weak_try_borrow_mut() {
+        let shared = Shared::new("Hello".to_string());
+        let weak = shared.downgrade();
+
+        let mut guard = weak.try_borrow_mut().unwrap().unwrap();
+        *guard += ", world!";
+        drop(guard);
+
+        assert_eq!(&*shared.borrow(), "Hello, world!");
+    }
+    #[test]
+    fn new_from_supports_unsizing_to_a_trait_object() {
+        let rc: Rc<RefCell<dyn fmt::Display>> = Rc::new(RefCell::new("Hello".to_string()));
+        let shared: Shared<dyn fmt::Display> = Shared::new_from(rc);
+
+        assert_eq!(format!("{}", shared.borrow()), "Hello");
+    }
+    #[test]
+    fn borrow_map() {
+        let shared = Shared::new(("Hello".to_string(), "world".to_string()));
+
+        assert_eq!(&*shared.borrow_map(|pair| &pair.0), "Hello");
+    }
+    #[test]
+    fn borrow_map_mut() {
+        let shared = Shared::new(("Hello".to_string(), "world".to_string()));
+
+        *shared.borrow_map_mut(|pair| &mut pair.1) = "there".to_string();
+
+        assert_eq!(&*shared.borrow().1, "there");
+    }
+    #[test]
+    fn group_try_borrow_fails_when_not_in_group() {
+        let shared = Shared::new("Hello".to_string());
+
+        assert_eq!(shared.group_try_borrow().unwrap_err(), group::MeBorrowError::NotInGroup);
+    }
+    #[test]
+    fn group_try_borrow_allows_concurrent_reads_across_cells() {
+        let group = group::SharedGroup::new();
+        let a = Shared::new_in_group(&group, "Hello".to_string());
+        let b = Shared::new_in_group(&group, "world".to_string());
+
+        let guard_a = a.group_try_borrow().unwrap();
+        let guard_b = b.group_try_borrow().unwrap();
+
+        assert_eq!(&*guard_a, "Hello");
+        assert_eq!(&*guard_b, "world");
+    }
+    #[test]
+    fn group_try_borrow_is_reentrant() {
+        let group = group::SharedGroup::new();
+        let a = Shared::new_in_group(&group, "Hello".to_string());
+
+        let _guard1 = a.group_try_borrow().unwrap();
+        let guard2 = a.group_try_borrow().unwrap();
+
+        assert_eq!(&*guard2, "Hello");
+    }
+    #[test]
+    fn group_try_borrow_mut_excludes_reads_across_the_group() {
+        let group = group::SharedGroup::new();
+        let a = Shared::new_in_group(&group, "Hello".to_string());
+        let b = Shared::new_in_group(&group, "world".to_string());
+
+        let _guard = a.group_try_borrow_mut().unwrap();
+
+        assert_eq!(b.group_try_borrow().unwrap_err(), group::MeBorrowError::Borrowed);
+    }
+    #[test]
+    fn group_try_borrow_mut_then_succeeds_after_guard_drops() {
+        let shared_group = group::SharedGroup::new();
+        let a = Shared::new_in_group(&shared_group, "Hello".to_string());
+
+        {
+            let mut guard = a.group_try_borrow_mut().unwrap();
+            *guard += ", world!";
+        }
+
+        assert_eq!(&*a.group_try_borrow().unwrap(), "Hello, world!");
+    }
+    #[test]
+    #[should_panic(expected = "new_in_group")]
+    fn borrow_panics_on_grouped_cell_with_a_live_group_borrow_mut() {
+        let group = group::SharedGroup::new();
+        let a = Shared::new_in_group(&group, "Hello".to_string());
+
+        let _guard = a.group_try_borrow_mut().unwrap();
+        // Without the group-awareness check, this would hand out a `&String` aliased with the
+        // `&mut String` the guard above already holds.
+        let _ = a.borrow();
+    }
+    #[test]
+    #[should_panic(expected = "new_in_group")]
+    fn borrow_mut_panics_on_grouped_cell() {
+        let group = group::SharedGroup::new();
+        let a = Shared::new_in_group(&group, "Hello".to_string());
+
+        let _ = a.borrow_mut();
+    }
+    #[test]
+    #[should_panic(expected = "new_in_group")]
+    fn try_borrow_panics_on_grouped_cell() {
+        let group = group::SharedGroup::new();
+        let a = Shared::new_in_group(&group, "Hello".to_string());
+
+        let _ = a.try_borrow();
+    }
+    #[test]
+    #[should_panic(expected = "new_in_group")]
+    fn try_borrow_mut_panics_on_grouped_cell() {
+        let group = group::SharedGroup::new();
+        let a = Shared::new_in_group(&group, "Hello".to_string());
+
+        let _ = a.try_borrow_mut();
+    }
+    #[test]
+    #[should_panic(expected = "new_in_group")]
+    fn weak_try_borrow_panics_on_grouped_cell_with_a_live_group_borrow_mut() {
+        let group = group::SharedGroup::new();
+        let a = Shared::new_in_group(&group, "Hello".to_string());
+        let weak = a.downgrade();
+
+        let _guard = a.group_try_borrow_mut().unwrap();
+        // Without the group-awareness check, this would hand out a `&String` aliased with the
+        // `&mut String` the guard above already holds.
+        let _ = weak.try_borrow();
+    }
+    #[test]
+    #[should_panic(expected = "new_in_group")]
+    fn weak_try_borrow_mut_panics_on_grouped_cell() {
+        let group = group::SharedGroup::new();
+        let a = Shared::new_in_group(&group, "Hello".to_string());
+        let weak = a.downgrade();
+
+        let _ = weak.try_borrow_mut();
+    }
 }